@@ -1,15 +1,41 @@
 use regex::Regex;
 use reqwest;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
 use std::process::exit;
 
+// Default per-chunk codepoint budget, overridable with `--chunk-size`/GT_CHUNK_SIZE. Kept well
+// under the real per-request limit below so a handful of oversized paragraphs don't push a
+// chunk over it.
+const DEFAULT_CHUNK_CODEPOINT_BUDGET: usize = 5000;
+
+// Google recommends keeping a single translate request under ~30k codepoints. Chunks are
+// batched into requests that stay under this so large inputs (an article, a resume) still go
+// out as multiple requests instead of one oversized one that Google rejects or mangles.
+const REQUEST_CODEPOINT_BUDGET: usize = 30000;
+
+// Which translation API to talk to. Google is the default and requires a GOOGLE_ACCESS_KEY;
+// LibreTranslate is keyless and points at a configurable (possibly self-hosted) server.
+#[derive(Clone, Copy, PartialEq)]
+enum Backend {
+    Google,
+    LibreTranslate,
+}
+
 // CLI inputs
 struct Input {
     input_language: String,
     output_language: String,
     text: String,
+    out_path: Option<String>,
+    backend: Backend,
+    glossary: Vec<(String, String)>,
+    glossary_ignore_case: bool,
+    format: String,
+    chunk_size: usize,
 }
 
 // For deserializing API response from google
@@ -17,6 +43,7 @@ struct Input {
 #[derive(Deserialize)]
 struct Translated {
     translatedText: String,
+    detectedSourceLanguage: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -30,6 +57,35 @@ struct Ip {
     data: Translations,
 }
 
+// Body sent to the v2 endpoint. `source` is omitted entirely when empty so Google auto-detects
+// the input language, and `q` is always an array so multi-chunk translations round-trip in order.
+#[derive(Serialize)]
+struct TranslateBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    target: String,
+    q: Vec<String>,
+    format: String,
+}
+
+// Body sent to a LibreTranslate-compatible `/translate` endpoint. Unlike the Google v2 API, `q`
+// is a single string, so chunked input is sent as one request per chunk.
+#[derive(Serialize)]
+struct LibreTranslateBody {
+    q: String,
+    source: String,
+    target: String,
+    format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct LibreTranslateResponse {
+    translatedText: String,
+}
+
 fn get_optional_env_var(key: &str) -> String {
     return match env::var_os(key) {
         Some(val) => match val.into_string() {
@@ -40,16 +96,129 @@ fn get_optional_env_var(key: &str) -> String {
     };
 }
 
+// Loads a simple CSV/TSV glossary of `source_term,target_term` (or tab-separated) pairs.
+fn load_glossary(path: &str) -> Vec<(String, String)> {
+    let contents = fs::read_to_string(path).unwrap_or_else(|e| {
+        println!("Failed to read glossary file {}: {}", path, e);
+        exit(1);
+    });
+
+    contents
+        .lines()
+        .filter(|line| line.trim().len() > 0)
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, |c| c == ',' || c == '\t');
+            let source_term = parts.next()?.trim().to_string();
+            let target_term = parts.next()?.trim().to_string();
+            if source_term.len() > 0 && target_term.len() > 0 {
+                Some((source_term, target_term))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Replaces whole-word occurrences of `term` with `replacement` in `text`. "Whole word" is
+// decided by inspecting the characters immediately surrounding each match rather than relying
+// on regex `\b`, which never fires between two non-word characters - that would silently skip
+// symbol-edged terms like "C++" or ".NET" when bordered by punctuation instead of whitespace.
+// Building the output by literal concatenation also means the replacement is never run through
+// regex `$`-expansion, so a target term containing `$` (e.g. "US$") is inserted as-is.
+fn replace_whole_word(text: &str, term: &str, replacement: &str, ignore_case: bool) -> String {
+    let escaped = regex::escape(term);
+    let pattern = if ignore_case {
+        format!("(?i){}", escaped)
+    } else {
+        escaped
+    };
+    let re = Regex::new(&pattern).unwrap();
+
+    let mut result = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for m in re.find_iter(text) {
+        let before_ok = text[..m.start()]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !is_word_char(c));
+        let after_ok = text[m.end()..]
+            .chars()
+            .next()
+            .map_or(true, |c| !is_word_char(c));
+        if before_ok && after_ok {
+            result.push_str(&text[last_end..m.start()]);
+            result.push_str(replacement);
+            last_end = m.end();
+        }
+    }
+    result.push_str(&text[last_end..]);
+    result
+}
+
+// Enforces glossary terms on already-translated text: machine translation often leaves brand
+// names or technical terms untranslated, so each glossary `source_term` that survived is
+// whole-word replaced with its designated `target_term`.
+fn apply_glossary(text: &str, glossary: &[(String, String)], ignore_case: bool) -> String {
+    let mut result = text.to_string();
+    for (source_term, target_term) in glossary {
+        result = replace_whole_word(&result, source_term, target_term, ignore_case);
+    }
+    result
+}
+
+// Reads all of stdin into a string, used when the user pipes text in instead of passing it
+// as an argument or a `-f`/`--file` path, e.g. `cat resume.txt | google-translate -o fr`.
+fn read_stdin() -> String {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .unwrap_or_else(|e| {
+            println!("Failed to read from stdin: {}", e);
+            exit(1);
+        });
+    text.trim().to_string()
+}
+
 fn print_help() {
     println!(
         "
 To translate something something using google translate, use the format
 `google-translate -i <input_language> -o <output_language> <text to translate>.
 
+The input language is optional - if you leave off `-i <input_language>`, Google will
+auto-detect it for you and the detected language code will be printed alongside the result.
+When the result is written to disk with `--out` (or by default for `-f`), it is not shown.
+
 You may also provide the input language with the environment variable GT_INPUT_LANGUAGE
 and output language with environment variable GT_OUTPUT_LANGUAGE.
 
-This requires an environment variable GOOGLE_ACCESS_KEY which can be retrieved with `gcloud auth application-default print-access-token`
+Instead of passing text directly, you can translate a whole file with `-f <path>` / `--file <path>`,
+or pipe text in over stdin, e.g. `cat resume.txt | google-translate -o fr`. Use `--out <path>` to
+write the result to disk instead of printing it - this defaults to `<name>.<target_lang>.txt`
+when translating a file.
+
+By default this requires an environment variable GOOGLE_ACCESS_KEY which can be retrieved with
+`gcloud auth application-default print-access-token`. Pass `--backend libretranslate` (or set
+GT_BACKEND=libretranslate) to use a keyless LibreTranslate-compatible server instead - configure
+it with GT_LIBRETRANSLATE_URL (default https://libretranslate.com) and, if required,
+GT_LIBRETRANSLATE_API_KEY.
+
+Use `--glossary <path>` to enforce consistent terminology from a CSV/TSV file of
+`source_term,target_term` pairs. This is implemented as a local post-processing pass (whole-word
+replacement in the translated text) regardless of backend, not the Google v3 glossary API. Add
+`--glossary-ignore-case` to match glossary terms case-insensitively.
+
+Use `--format html` if the text to translate is an HTML fragment (default is `text`) - this
+keeps markup tags intact and only translates the text nodes, so a `.html` file can be piped
+straight through and come back as translated HTML.
+
+Long input is automatically split into chunks of at most 5000 codepoints each (preferring to
+break on paragraph or sentence boundaries) before translating, and reassembled afterward. Use
+`--chunk-size <n>` or GT_CHUNK_SIZE to change that per-chunk codepoint budget.
 
 The allowed languages are:
 
@@ -178,56 +347,56 @@ fn parse_input() -> Input {
         "ug", "uz", "vi", "cy", "xh", "yi", "yo", "zu",
     ];
 
-    // join arguments into one string
-    let args: String = env::args().skip(1).collect::<Vec<String>>().join(" ");
-
-    // Regex to check for possible input after -i, possible output after -o, the text to translate
-    let re = Regex::new(
-        r"^(-i (?P<input_language>[a-z]+))?(\s*-o (?P<output_language>[a-z]+))?(?P<text>.*)$",
-    )
-    .unwrap();
-
-    // check if user wants help
-    if args.contains("--help") {
-        print_help();
-    }
-
     // First check if an environment variable defines the input and output, but they can override these
     let mut input_language = get_optional_env_var("GT_INPUT_LANGUAGE");
     let mut output_language = get_optional_env_var("GT_OUTPUT_LANGUAGE");
+    let mut file_path: Option<String> = None;
+    let mut explicit_out_path: Option<String> = None;
+    let mut explicit_backend: Option<String> = None;
+    let mut glossary_path: Option<String> = None;
+    let mut glossary_ignore_case = false;
+    let mut explicit_format: Option<String> = None;
+    let mut explicit_chunk_size: Option<String> = None;
+    let mut text_words: Vec<String> = Vec::new();
 
-    let mut text = String::new();
-
-    match re.captures(&args) {
-        Some(val) => {
-            match val.name("input_language") {
-                Some(val) => input_language = val.as_str().to_string(),
-                None => (),
-            };
-            match val.name("output_language") {
-                Some(val) => output_language = val.as_str().to_string(),
-                None => (),
-            };
-            match val.name("text") {
-                Some(val) => text = val.as_str().trim().to_string(),
-                None => (),
-            };
+    // Walk the arguments positionally rather than regex-searching the joined string, so
+    // translatable text that happens to contain e.g. "--out" or "--glossary" is never mistaken
+    // for a flag - only a token that actually occupies a flag's position is treated as one.
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" => print_help(),
+            "-i" => {
+                if let Some(val) = args.next() {
+                    input_language = val;
+                }
+            }
+            "-o" => {
+                if let Some(val) = args.next() {
+                    output_language = val;
+                }
+            }
+            "-f" | "--file" => file_path = args.next(),
+            "--out" => explicit_out_path = args.next(),
+            "--backend" => explicit_backend = args.next(),
+            "--glossary" => glossary_path = args.next(),
+            "--glossary-ignore-case" => glossary_ignore_case = true,
+            "--format" => explicit_format = args.next(),
+            "--chunk-size" => explicit_chunk_size = args.next(),
+            word => text_words.push(word.to_string()),
         }
-        None => (),
     }
 
-    // Check that input and output languages provided and are allowed
-    if input_language.len() == 0 {
-        println!("No input language provided. Type --help to see allowed languages");
-        exit(1);
-    }
+    let mut text = text_words.join(" ");
 
+    // Check that output language is provided and allowed. Input language is optional -
+    // when omitted, Google will auto-detect it - but if one is given it must be allowed.
     if output_language.len() == 0 {
         println!("No output language provided. Type --help to see allowed languages");
         exit(1);
     }
 
-    if !allowed_languages.iter().any(|&i| i == input_language) {
+    if input_language.len() > 0 && !allowed_languages.iter().any(|&i| i == input_language) {
         println!("Input language is not allowed. Type --help to see allowed languages");
         exit(1);
     }
@@ -236,39 +405,369 @@ fn parse_input() -> Input {
         exit(1);
     }
 
+    // A `-f`/`--file` path takes precedence over any positional text; otherwise fall back to
+    // stdin when nothing was typed on the command line, so the CLI works in a pipeline.
+    if let Some(path) = &file_path {
+        text = fs::read_to_string(path).unwrap_or_else(|e| {
+            println!("Failed to read file {}: {}", path, e);
+            exit(1);
+        });
+    } else if text.len() == 0 {
+        text = read_stdin();
+    }
+
+    // `--out` writes the translation to disk instead of stdout. When translating a file without
+    // an explicit `--out`, default to `<name>.<target_lang>.txt` next to the source file.
+    let out_path = explicit_out_path.or_else(|| {
+        file_path.as_ref().map(|path| {
+            let mut out = PathBuf::from(path);
+            let stem = out
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output")
+                .to_string();
+            out.set_file_name(format!("{}.{}.txt", stem, output_language));
+            out.to_string_lossy().to_string()
+        })
+    });
+
+    // Backend defaults to Google, overridable with `--backend` or GT_BACKEND.
+    let backend_name = explicit_backend.unwrap_or_else(|| get_optional_env_var("GT_BACKEND"));
+    let backend = match backend_name.as_str() {
+        "" | "google" => Backend::Google,
+        "libretranslate" => Backend::LibreTranslate,
+        _ => {
+            println!(
+                "Unknown backend '{}'. Supported backends are \"google\" and \"libretranslate\".",
+                backend_name
+            );
+            exit(1);
+        }
+    };
+
+    let glossary = glossary_path
+        .as_ref()
+        .map(|path| load_glossary(path))
+        .unwrap_or_else(Vec::new);
+
+    // `--format` controls whether markup is preserved; defaults to plain text.
+    let format = explicit_format.unwrap_or_else(|| "text".to_string());
+    if format != "html" && format != "text" {
+        println!("Unknown format '{}'. Supported formats are \"html\" and \"text\".", format);
+        exit(1);
+    }
+
+    // Chunk size defaults to DEFAULT_CHUNK_CODEPOINT_BUDGET codepoints, overridable with
+    // `--chunk-size` or GT_CHUNK_SIZE. Must be a positive integer (0 makes chunk_text unable to
+    // make progress and recurse forever) and can't exceed REQUEST_CODEPOINT_BUDGET, since
+    // batch_segments assumes every segment already fits within a single request on its own.
+    let chunk_size_str = explicit_chunk_size.unwrap_or_else(|| get_optional_env_var("GT_CHUNK_SIZE"));
+    let chunk_size = if chunk_size_str.len() == 0 {
+        DEFAULT_CHUNK_CODEPOINT_BUDGET
+    } else {
+        let parsed = chunk_size_str.parse::<usize>().unwrap_or(0);
+        if parsed == 0 || parsed > REQUEST_CODEPOINT_BUDGET {
+            println!(
+                "Invalid --chunk-size '{}': must be a positive integer no greater than {}.",
+                chunk_size_str, REQUEST_CODEPOINT_BUDGET
+            );
+            exit(1);
+        }
+        parsed
+    };
+
     Input {
         input_language: input_language,
         output_language: output_language,
         text: text,
+        out_path: out_path,
+        backend: backend,
+        glossary: glossary,
+        glossary_ignore_case: glossary_ignore_case,
+        format: format,
+        chunk_size: chunk_size,
     }
 }
 
-fn translate(input: Input) {
+// Decodes a single HTML entity match (e.g. "&#39;", "&amp;") into its character, or `None` if
+// it isn't one we recognize - the caller leaves those untouched rather than dropping them.
+fn decode_html_entity(entity: &str) -> Option<String> {
+    let inner = &entity[1..entity.len() - 1];
+
+    if let Some(digits) = inner.strip_prefix('#') {
+        let code_point = if let Some(hex) = digits.strip_prefix('x').or_else(|| digits.strip_prefix('X')) {
+            u32::from_str_radix(hex, 16).ok()?
+        } else {
+            digits.parse::<u32>().ok()?
+        };
+        return char::from_u32(code_point).map(|c| c.to_string());
+    }
+
+    let decoded = match inner {
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "quot" => "\"",
+        "apos" => "'",
+        _ => return None,
+    };
+    Some(decoded.to_string())
+}
+
+// The v2 endpoint HTML-escapes translatedText, so apostrophes and quotes come back as
+// `&#39;`/`&quot;` etc. This resolves both numeric and the common named entities in one pass.
+fn unescape_html_entities(text: &str) -> String {
+    let re = Regex::new(r"&#?\w+;").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| {
+        let entity = &caps[0];
+        decode_html_entity(entity).unwrap_or_else(|| entity.to_string())
+    })
+    .to_string()
+}
+
+// Finds the last occurrence of `separator` at or before `budget` codepoints into `text`,
+// returning (end of the segment before it, end of the separator itself) so the caller can
+// split the segment from the separator instead of keeping them fused together.
+fn find_split_point(text: &str, separator: &str, budget: usize) -> Option<(usize, usize)> {
+    let byte_limit = text
+        .char_indices()
+        .nth(budget)
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| text.len());
+    text[..byte_limit]
+        .rfind(separator)
+        .map(|i| (i, i + separator.len()))
+}
+
+// Finds where to hard-cut `text` at or before `budget` codepoints in. For HTML, cutting inside a
+// `<...>` tag would scatter half a tag into each chunk and reassemble into broken markup, so the
+// cut is backed up to just before the tag's opening `<` when the naive cut point would land
+// inside one. Falls back to the naive cut if that would make no progress (e.g. the tag itself
+// starts at the very beginning of `text`).
+fn safe_hard_cut(text: &str, budget: usize, is_html: bool) -> usize {
+    let byte_limit = text
+        .char_indices()
+        .nth(budget)
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| text.len());
+
+    if !is_html {
+        return byte_limit;
+    }
+
+    let before = &text[..byte_limit];
+    match (before.rfind('<'), before.rfind('>')) {
+        (Some(lt), gt) if lt > 0 && gt.map_or(true, |gt| gt < lt) => lt,
+        _ => byte_limit,
+    }
+}
+
+// Splits `text` into (segment, separator) pairs that each stay under `budget` codepoints,
+// preferring to break on paragraph boundaries, then sentence boundaries, and only hard-cutting a
+// segment that still exceeds the budget on its own. The separator is kept apart from the segment
+// (rather than left attached to it) so callers can send the segment alone for translation and
+// re-insert the original separator verbatim when reassembling, instead of trusting the
+// translation API to preserve trailing whitespace it's free to trim.
+fn chunk_text(text: &str, budget: usize, is_html: bool) -> Vec<(String, String)> {
+    if text.chars().count() <= budget {
+        return vec![(text.to_string(), String::new())];
+    }
+
+    if let Some((segment_end, separator_end)) =
+        find_split_point(text, "\n\n", budget).or_else(|| find_split_point(text, ". ", budget))
+    {
+        let segment = text[..segment_end].to_string();
+        let separator = text[segment_end..separator_end].to_string();
+        let mut chunks = vec![(segment, separator)];
+        chunks.extend(chunk_text(&text[separator_end..], budget, is_html));
+        return chunks;
+    }
+
+    // No paragraph or sentence boundary within budget, so this segment has to be cut mid-text -
+    // that necessarily fragments it (a sentence, or for HTML potentially a tag boundary), so say
+    // so rather than silently doing it.
+    println!("Note: a translation chunk exceeded the size limit with no sentence boundary to split on; cutting it mid-text.");
+    let hard_cut = safe_hard_cut(text, budget, is_html);
+    let mut chunks = vec![(text[..hard_cut].to_string(), String::new())];
+    chunks.extend(chunk_text(&text[hard_cut..], budget, is_html));
+    chunks
+}
+
+// Groups `segments` into batches whose cumulative codepoint count stays under `budget`, so a
+// long input that chunk_text already split into many small segments still goes out as few
+// requests rather than one per segment. Each segment is already within the per-chunk budget
+// (far smaller than `budget` here), so it always fits into some batch on its own.
+fn batch_segments(segments: Vec<String>, budget: usize) -> Vec<Vec<String>> {
+    let mut batches = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_len = 0;
+
+    for segment in segments {
+        let len = segment.chars().count();
+        if !current.is_empty() && current_len + len > budget {
+            batches.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current_len += len;
+        current.push(segment);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+// Translates via the Google v2 endpoint. Requires GOOGLE_ACCESS_KEY and returns whatever
+// language it auto-detected when `source` was left unset, along with one translated string per
+// input segment (in order) - the caller re-inserts the separators that were split on. Segments
+// are sent in batches that stay under REQUEST_CODEPOINT_BUDGET rather than all in one request,
+// since the real per-request limit is on the total request size, not any single chunk.
+fn translate_with_google(source: Option<String>, target: String, segments: Vec<String>, format: String) -> (Vec<String>, Option<String>) {
     let access_key = get_optional_env_var("GOOGLE_ACCESS_KEY");
     if access_key.len() == 0 {
         println!("A Google access key is required. See this for how to create one: https://cloud.google.com/translate/docs/setup");
         exit(1);
     }
 
-    // Build body for http call
-    let mut body = HashMap::new();
-    body.insert("source", input.input_language);
-    body.insert("target", input.output_language);
-    body.insert("q", input.text);
+    let client = reqwest::blocking::Client::new();
+    let mut translated_segments = Vec::new();
+    let mut detected_language = None;
+
+    for batch in batch_segments(segments, REQUEST_CODEPOINT_BUDGET) {
+        let body = TranslateBody {
+            source: source.clone(),
+            target: target.clone(),
+            q: batch,
+            format: format.clone(),
+        };
+
+        let res: Result<Ip, reqwest::Error> = client
+            .post("https://translation.googleapis.com/language/translate/v2")
+            .header("Authorization", format!("Bearer {}", access_key))
+            .json(&body)
+            .send()
+            .unwrap()
+            .json();
+
+        match res {
+            Ok(res) => {
+                if detected_language.is_none() {
+                    detected_language = res.data.translations[0].detectedSourceLanguage.clone();
+                }
+                // Google HTML-escapes text-node content even in html mode, but unescaping there
+                // would turn e.g. `&lt;` back into `<` and corrupt the markup - only decode
+                // entities for plain text, where there's no markup to protect.
+                for t in &res.data.translations {
+                    if format == "text" {
+                        translated_segments.push(unescape_html_entities(&t.translatedText));
+                    } else {
+                        translated_segments.push(t.translatedText.clone());
+                    }
+                }
+            }
+            Err(e) => {
+                println!("There was the following error with the API call: {}", e);
+                exit(1);
+            }
+        }
+    }
+
+    (translated_segments, detected_language)
+}
+
+// Translates via a LibreTranslate-compatible server, keyless by default. The server only
+// accepts a single `q` string per request, so each segment is sent as its own call.
+fn translate_with_libretranslate(source: Option<String>, target: String, segments: Vec<String>, format: String) -> (Vec<String>, Option<String>) {
+    let mut base_url = get_optional_env_var("GT_LIBRETRANSLATE_URL");
+    if base_url.len() == 0 {
+        base_url = "https://libretranslate.com".to_string();
+    }
+    let api_key = get_optional_env_var("GT_LIBRETRANSLATE_API_KEY");
 
-    // Make call
     let client = reqwest::blocking::Client::new();
-    let res: Result<Ip, reqwest::Error> = client
-        .post("https://translation.googleapis.com/language/translate/v2")
-        .header("Authorization", format!("Bearer {}", access_key))
-        .json(&body)
-        .send()
-        .unwrap()
-        .json();
-
-    match res {
-        Ok(res) => println!("{}", res.data.translations[0].translatedText),
-        Err(e) => println!("There was the following error with the API call: {}", e),
+    let mut translated_segments = Vec::new();
+
+    for segment in segments {
+        let body = LibreTranslateBody {
+            q: segment,
+            source: source.clone().unwrap_or_else(|| "auto".to_string()),
+            target: target.clone(),
+            format: format.clone(),
+            api_key: if api_key.len() > 0 {
+                Some(api_key.clone())
+            } else {
+                None
+            },
+        };
+
+        let res: Result<LibreTranslateResponse, reqwest::Error> = client
+            .post(format!("{}/translate", base_url))
+            .json(&body)
+            .send()
+            .unwrap()
+            .json();
+
+        match res {
+            Ok(res) => {
+                // Same entity-escaping behavior as the Google backend: only unescape when the
+                // response is plain text, not when it's HTML markup.
+                if format == "text" {
+                    translated_segments.push(unescape_html_entities(&res.translatedText));
+                } else {
+                    translated_segments.push(res.translatedText);
+                }
+            }
+            Err(e) => {
+                println!("There was the following error with the API call: {}", e);
+                exit(1);
+            }
+        }
+    }
+
+    (translated_segments, None)
+}
+
+fn translate(input: Input) {
+    let out_path = input.out_path;
+    let glossary = input.glossary;
+    let glossary_ignore_case = input.glossary_ignore_case;
+    let source = if input.input_language.len() > 0 {
+        Some(input.input_language)
+    } else {
+        None
+    };
+    let target = input.output_language;
+    let format = input.format;
+
+    // The separator between each pair of chunks is tracked apart from the chunk text itself
+    // and re-inserted verbatim below, rather than trusting the API to preserve whatever
+    // trailing whitespace it was sent - translation endpoints commonly trim it.
+    let (segments, separators): (Vec<String>, Vec<String>) =
+        chunk_text(&input.text, input.chunk_size, format == "html")
+            .into_iter()
+            .unzip();
+
+    let (translated_segments, detected_language) = match input.backend {
+        Backend::Google => translate_with_google(source, target, segments, format),
+        Backend::LibreTranslate => translate_with_libretranslate(source, target, segments, format),
+    };
+
+    let translated_text: String = translated_segments
+        .iter()
+        .zip(separators.iter())
+        .map(|(segment, separator)| format!("{}{}", segment, separator))
+        .collect();
+    let translated_text = apply_glossary(&translated_text, &glossary, glossary_ignore_case);
+
+    match out_path {
+        Some(path) => match fs::write(&path, &translated_text) {
+            Ok(()) => println!("Wrote translation to {}", path),
+            Err(e) => println!("Failed to write to {}: {}", path, e),
+        },
+        None => match detected_language {
+            Some(lang) => println!("({}) {}", lang, translated_text),
+            None => println!("{}", translated_text),
+        },
     }
 }
 